@@ -0,0 +1,67 @@
+//! TTY-aware ANSI coloring for status markers.
+//!
+//! Colors are enabled when output still goes to a real stderr terminal
+//! (see [`crate::sink::is_default_terminal`]), unless disabled via the
+//! `NO_COLOR` convention or forced either way with `LOG_COLOR=always|never`.
+//! When disabled, text passes through unchanged so log-scraping tools see
+//! the same layout as before. Once output is redirected with
+//! `sink::set_writer`/`sink::capture`, color is off unless forced with
+//! `LOG_COLOR=always` — there's no way to know whether the new destination
+//! is a terminal.
+
+use std::env;
+use std::sync::OnceLock;
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ColorMode {
+    Always,
+    Never,
+    Auto,
+}
+
+fn color_mode() -> ColorMode {
+    static MODE: OnceLock<ColorMode> = OnceLock::new();
+    *MODE.get_or_init(|| match env::var("LOG_COLOR").as_deref() {
+        Ok("always") => ColorMode::Always,
+        Ok("never") => ColorMode::Never,
+        _ => ColorMode::Auto,
+    })
+}
+
+fn enabled() -> bool {
+    match color_mode() {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => {
+            env::var_os("NO_COLOR").is_none() && crate::sink::is_default_terminal()
+        }
+    }
+}
+
+fn paint(code: &str, text: &str) -> String {
+    if enabled() {
+        format!("{code}{text}{RESET}")
+    } else {
+        text.to_string()
+    }
+}
+
+/// Color for success markers (`[ok]`).
+pub(crate) fn ok(text: &str) -> String {
+    paint(GREEN, text)
+}
+
+/// Color for failure/error markers (`[fail]`, error actions).
+pub(crate) fn fail(text: &str) -> String {
+    paint(RED, text)
+}
+
+/// Color for warning markers (`[warn]`).
+pub(crate) fn warn(text: &str) -> String {
+    paint(YELLOW, text)
+}
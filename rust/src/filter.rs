@@ -0,0 +1,95 @@
+//! Per-target log filtering, `env_logger`/`RUST_LOG`-style.
+//!
+//! A `LOG_FILTER` string of comma-separated `target=level` entries, e.g.
+//! `info,cache=debug,db=error`, is parsed once into an ordered list of
+//! `(target_prefix, LogLevel)` rules plus an optional default level. An
+//! entry with no `=` sets that default. At emit time the level for an
+//! action/component name is the level of the *longest* matching target
+//! prefix, so with `db=error,db::pool=debug` the more specific `db::pool=debug`
+//! applies to `db::pool` (and `db::pool::*`) while `db=error` applies to the
+//! rest of `db`.
+//!
+//! If `LOG_FILTER` is unset, or its directives don't include a bare default,
+//! the default level falls back to [`crate::log_level`] (seeded from
+//! `LOG_LEVEL`), so [`crate::set_log_level`]/[`crate::with_log_level`]
+//! transparently affect any action with no more specific directive.
+
+use std::env;
+use std::sync::OnceLock;
+
+use crate::LogLevel;
+
+struct Filter {
+    directives: Vec<(String, LogLevel)>,
+    default: Option<LogLevel>,
+}
+
+static FILTER: OnceLock<Filter> = OnceLock::new();
+
+fn filter() -> &'static Filter {
+    FILTER.get_or_init(|| parse(&env::var("LOG_FILTER").unwrap_or_default()))
+}
+
+fn parse(spec: &str) -> Filter {
+    let mut directives = Vec::new();
+    let mut default = None;
+    for part in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        match part.split_once('=') {
+            Some((target, level)) => {
+                directives.push((target.trim().to_string(), LogLevel::from_str(level.trim())))
+            }
+            None => default = Some(LogLevel::from_str(part)),
+        }
+    }
+    Filter { directives, default }
+}
+
+impl Filter {
+    /// The level of the longest target prefix matching `action`, or the
+    /// default level (falling back to [`crate::log_level`]) if nothing matches.
+    fn level_for(&self, action: &str) -> LogLevel {
+        self.directives
+            .iter()
+            .filter(|(target, _)| {
+                action == target.as_str() || action.starts_with(&format!("{}::", target))
+            })
+            .max_by_key(|(target, _)| target.len())
+            .map(|&(_, level)| level)
+            .unwrap_or_else(|| self.default.unwrap_or_else(crate::log_level))
+    }
+}
+
+/// Check whether a message at `level` for the given `action`/target should
+/// be emitted, honoring `LOG_FILTER` (or `LOG_LEVEL`) directives.
+pub fn enabled_for(action: &str, level: LogLevel) -> bool {
+    filter().level_for(action) >= level
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_only() {
+        let f = parse("debug");
+        assert_eq!(f.level_for("anything"), LogLevel::Debug);
+    }
+
+    #[test]
+    fn longest_prefix_wins() {
+        let f = parse("info,db=error,db::pool=debug");
+        assert_eq!(f.level_for("db"), LogLevel::Error);
+        assert_eq!(f.level_for("db::other"), LogLevel::Error);
+        assert_eq!(f.level_for("db::pool"), LogLevel::Debug);
+        assert_eq!(f.level_for("cache"), LogLevel::Info);
+    }
+
+    #[test]
+    fn unset_default_tracks_runtime_log_level() {
+        let f = parse("cache=error");
+        crate::with_log_level(LogLevel::Debug, || {
+            assert_eq!(f.level_for("anything"), LogLevel::Debug);
+            assert_eq!(f.level_for("cache"), LogLevel::Error);
+        });
+    }
+}
@@ -0,0 +1,207 @@
+//! Structured key-value fields and the `text`/`json` output format switch.
+//!
+//! Modeled after the `log` crate's `kv` module: an event carries a message
+//! plus a slice of typed `(key, Value)` pairs. The active [`OutputFormat`]
+//! decides whether those pairs are appended as `key=value` text or folded
+//! into a single JSON line.
+
+use std::env;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Output format for log events, selected via the `LOG_FORMAT` env var.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum OutputFormat {
+    /// `[action] message key=value ...` (default)
+    Text,
+    /// `{"level":"info","action":"...","message":"...","key":value,...}`
+    Json,
+}
+
+static LOG_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+fn output_format() -> OutputFormat {
+    *LOG_FORMAT.get_or_init(|| match env::var("LOG_FORMAT") {
+        Ok(s) if s.eq_ignore_ascii_case("json") => OutputFormat::Json,
+        _ => OutputFormat::Text,
+    })
+}
+
+/// A structured field value attached to a log event.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl Value {
+    fn to_json(&self) -> String {
+        match self {
+            Value::Str(s) => format!("\"{}\"", json_escape(s)),
+            Value::Int(i) => i.to_string(),
+            // JSON has no NaN/Infinity tokens; fall back to `null` like
+            // `JSON.stringify` does, rather than emitting invalid JSON.
+            Value::Float(f) if !f.is_finite() => "null".to_string(),
+            Value::Float(f) => f.to_string(),
+            Value::Bool(b) => b.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Str(s) => write!(f, "{}", s),
+            Value::Int(i) => write!(f, "{}", i),
+            Value::Float(v) => write!(f, "{}", v),
+            Value::Bool(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+macro_rules! impl_value_from {
+    ($variant:ident, $($ty:ty),+ $(,)?) => {
+        $(impl From<$ty> for Value {
+            fn from(v: $ty) -> Self {
+                Value::$variant(v.into())
+            }
+        })+
+    };
+}
+
+impl_value_from!(Int, i8, i16, i32, i64, u8, u16, u32);
+impl_value_from!(Float, f32, f64);
+impl_value_from!(Bool, bool);
+
+impl From<&str> for Value {
+    fn from(v: &str) -> Self {
+        Value::Str(v.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(v: String) -> Self {
+        Value::Str(v)
+    }
+}
+
+/// Escape a string for embedding in a JSON string literal. Escapes the
+/// standard two-character sequences (including `\r`, which plain `\n`/`\t`
+/// handling misses) and any other control character as `\uXXXX`, so the
+/// result is always valid JSON regardless of what a caller logs.
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0C}' => out.push_str("\\f"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Build the JSON line for one log event, without emitting it.
+fn render_json(level: &str, action: &str, message: &str, fields: &[(&str, Value)]) -> String {
+    let mut out = format!(
+        "{{\"level\":\"{}\",\"action\":\"{}\",\"message\":\"{}\"",
+        level,
+        json_escape(action),
+        json_escape(message)
+    );
+    for (key, value) in fields {
+        out.push_str(&format!(",\"{}\":{}", key, value.to_json()));
+    }
+    out.push('}');
+    out
+}
+
+/// Render and emit one log event, honoring the active [`OutputFormat`].
+///
+/// Not part of the public API; called by the `logf!`/`errorf!`/`debugf!`
+/// macros, which is why it takes the already-formatted `level` and `message`.
+#[doc(hidden)]
+pub fn __emit(level: &str, action: &str, message: &str, fields: &[(&str, Value)]) {
+    match output_format() {
+        OutputFormat::Json => {
+            crate::sink::emit_line(&render_json(level, action, message, fields));
+        }
+        OutputFormat::Text => {
+            let prefix = if level == "error" {
+                crate::color::fail(action)
+            } else {
+                action.to_string()
+            };
+            if fields.is_empty() {
+                crate::sink::emit_line(&format!("[{}] {}", prefix, message));
+            } else {
+                let rendered: Vec<String> =
+                    fields.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+                crate::sink::emit_line(&format!("[{}] {} {}", prefix, message, rendered.join(" ")));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_escape_handles_standard_sequences() {
+        assert_eq!(json_escape("a\"b\\c\nd\te"), "a\\\"b\\\\c\\nd\\te");
+    }
+
+    #[test]
+    fn json_escape_handles_carriage_return_and_other_control_chars() {
+        assert_eq!(json_escape("cr\rlf"), "cr\\rlf");
+        assert_eq!(json_escape("\u{1}"), "\\u0001");
+    }
+
+    #[test]
+    fn value_to_json_escapes_strings() {
+        assert_eq!(Value::from("x\ry").to_json(), "\"x\\ry\"");
+        assert_eq!(Value::from(42i64).to_json(), "42");
+        assert_eq!(Value::from(true).to_json(), "true");
+    }
+
+    #[test]
+    fn value_to_json_maps_non_finite_floats_to_null() {
+        assert_eq!(Value::from(f64::NAN).to_json(), "null");
+        assert_eq!(Value::from(f64::INFINITY).to_json(), "null");
+        assert_eq!(Value::from(f64::NEG_INFINITY).to_json(), "null");
+        assert_eq!(Value::from(1.5f64).to_json(), "1.5");
+    }
+
+    #[test]
+    fn render_json_is_always_valid_json() {
+        let line = render_json(
+            "info",
+            "build",
+            "bad\r\nmessage",
+            &[("note", Value::from("x\u{1}y"))],
+        );
+        assert_eq!(
+            line,
+            "{\"level\":\"info\",\"action\":\"build\",\"message\":\"bad\\r\\nmessage\",\"note\":\"x\\u0001y\"}"
+        );
+        assert!(!line.chars().any(|c| c.is_control()));
+    }
+
+    #[test]
+    fn render_json_maps_non_finite_float_field_to_null() {
+        let line = render_json("info", "build", "done", &[("ratio", Value::from(f64::NAN))]);
+        assert_eq!(
+            line,
+            "{\"level\":\"info\",\"action\":\"build\",\"message\":\"done\",\"ratio\":null}"
+        );
+    }
+}
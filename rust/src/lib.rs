@@ -25,10 +25,68 @@
 //! - `error` - Errors only
 //! - `info` - Default (startup + important messages)
 //! - `debug` - Verbose output
+//!
+//! ## `log` Facade
+//!
+//! Libraries that already emit through the standard [`log`] macros can route
+//! through the same `[action] message` formatting via [`logger::init`]. See
+//! the [`logger`] module for details.
+//!
+//! ## Structured Fields & JSON Output
+//!
+//! `logf!`, `errorf!` and `debugf!` accept typed key-value pairs before the
+//! message, separated by a `;`:
+//!
+//! ```rust
+//! tana_stdio::logf!("build", files = 42, ms = 150; "compiled");
+//! // Output: [build] compiled files=42 ms=150
+//! ```
+//!
+//! Set `LOG_FORMAT=json` to emit `{"level":"info","action":"build","message":"compiled","files":42,"ms":150}`
+//! instead, for services running under a log collector. See the [`kv`] module.
+//!
+//! ## Per-Target Filtering
+//!
+//! For finer control than the global `LOG_LEVEL`, set `LOG_FILTER` to a
+//! comma-separated list of `target=level` directives, e.g.
+//! `info,cache=debug,db=error`. The longest matching target prefix against
+//! the `action` wins; an entry with no `=` sets the default (falling back to
+//! [`log_level`] if omitted). See the [`filter`] module.
+//!
+//! ## Color
+//!
+//! `[ok]`/success prefixes print green, `[fail]`/error prefixes print red,
+//! and `[warn]` prefixes print yellow when stderr is a terminal. Disable
+//! with `NO_COLOR` or force either way with `LOG_COLOR=always|never`.
+//!
+//! ## Output Sink
+//!
+//! All output is written through a swappable sink (stderr by default). Use
+//! [`sink::set_writer`] to redirect to a file, or [`sink::capture`] to
+//! collect output into an in-memory buffer for tests.
+//!
+//! ## Runtime Log Level
+//!
+//! [`log_level`] starts from `LOG_LEVEL` but can be changed live with
+//! [`set_log_level`], or temporarily with [`with_log_level`] for the
+//! duration of a closure (e.g. a CLI `--verbose` flag).
+//! Both are scoped to the calling thread, so concurrent callers (e.g.
+//! overlapping request handlers in a service) can't race on a shared level.
+//! A level set on one thread is not seen by any other thread; there is no
+//! process-wide runtime level.
 
+use std::cell::Cell;
 use std::env;
 use std::sync::OnceLock;
 
+mod color;
+pub mod filter;
+pub mod kv;
+pub mod logger;
+pub mod sink;
+
+pub use kv::Value;
+
 /// Log level for tana services
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum LogLevel {
@@ -38,7 +96,7 @@ pub enum LogLevel {
 }
 
 impl LogLevel {
-    fn from_str(s: &str) -> Self {
+    pub(crate) fn from_str(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "error" => LogLevel::Error,
             "debug" => LogLevel::Debug,
@@ -47,17 +105,73 @@ impl LogLevel {
     }
 }
 
-static LOG_LEVEL: OnceLock<LogLevel> = OnceLock::new();
+/// Caches the `LOG_LEVEL` env var exactly once, as the initial value for
+/// every thread's [`LOG_LEVEL`].
+static ENV_LOG_LEVEL: OnceLock<LogLevel> = OnceLock::new();
 
-/// Get the current log level (cached from LOG_LEVEL env var)
-pub fn log_level() -> LogLevel {
-    *LOG_LEVEL.get_or_init(|| {
+fn env_log_level() -> LogLevel {
+    *ENV_LOG_LEVEL.get_or_init(|| {
         env::var("LOG_LEVEL")
             .map(|s| LogLevel::from_str(&s))
             .unwrap_or(LogLevel::Info)
     })
 }
 
+thread_local! {
+    /// Per-thread override, set via [`set_log_level`]/[`with_log_level`].
+    /// `None` means "fall back to the `LOG_LEVEL` env default". Scoped to the
+    /// thread rather than a process-wide atomic so concurrent callers (e.g.
+    /// overlapping request handlers) can't race or stomp each other's
+    /// restore on exit.
+    static LOG_LEVEL: Cell<Option<LogLevel>> = const { Cell::new(None) };
+}
+
+/// Get the current log level for the calling thread.
+///
+/// Starts from the `LOG_LEVEL` env var and can be changed at runtime with
+/// [`set_log_level`] or [`with_log_level`], which affect only the calling
+/// thread.
+pub fn log_level() -> LogLevel {
+    LOG_LEVEL.with(|cell| cell.get()).unwrap_or_else(env_log_level)
+}
+
+/// Set the log level for the calling thread, overriding the `LOG_LEVEL` env
+/// var until the thread exits or [`set_log_level`] is called again on it.
+///
+/// This only affects the calling thread; other threads keep their own level.
+///
+/// # Example
+/// ```
+/// tana_stdio::set_log_level(tana_stdio::LogLevel::Debug);
+/// assert!(tana_stdio::is_debug());
+/// ```
+pub fn set_log_level(level: LogLevel) {
+    LOG_LEVEL.with(|cell| cell.set(Some(level)));
+}
+
+/// Run `f` with the calling thread's log level temporarily raised or
+/// lowered to `level`, restoring the previous level afterward (even if `f`
+/// panics). Other threads are unaffected.
+///
+/// # Example
+/// ```
+/// tana_stdio::with_log_level(tana_stdio::LogLevel::Debug, || {
+///     tana_stdio::debug("cache", "hit for key: user_123");
+/// });
+/// ```
+pub fn with_log_level<T>(level: LogLevel, f: impl FnOnce() -> T) -> T {
+    struct RestoreOnDrop(LogLevel);
+    impl Drop for RestoreOnDrop {
+        fn drop(&mut self) {
+            set_log_level(self.0);
+        }
+    }
+
+    let _restore = RestoreOnDrop(log_level());
+    set_log_level(level);
+    f()
+}
+
 /// Check if debug logging is enabled
 pub fn is_debug() -> bool {
     log_level() >= LogLevel::Debug
@@ -81,8 +195,8 @@ pub fn is_info() -> bool {
 /// // Output: [build] compiling contract...
 /// ```
 pub fn log(action: &str, message: &str) {
-    if log_level() >= LogLevel::Info {
-        eprintln!("[{}] {}", action, message);
+    if filter::enabled_for(action, LogLevel::Info) {
+        sink::emit_line(&format!("[{}] {}", action, message));
     }
 }
 
@@ -95,7 +209,7 @@ pub fn log(action: &str, message: &str) {
 /// // Output: [build] compilation failed
 /// ```
 pub fn error(action: &str, message: &str) {
-    eprintln!("[{}] {}", action, message);
+    sink::emit_line(&format!("[{}] {}", color::fail(action), message));
 }
 
 /// Log a warning
@@ -107,13 +221,13 @@ pub fn error(action: &str, message: &str) {
 /// // Output: [warn] [cache] stale entries detected
 /// ```
 pub fn warn(name: &str, message: &str) {
-    eprintln!("[warn] [{}] {}", name, message);
+    sink::emit_line(&format!("[{}] [{}] {}", color::warn("warn"), name, message));
 }
 
 /// Log a simple warning without component name
 /// Format: `[warn] message`
 pub fn warn_simple(message: &str) {
-    eprintln!("[warn] {}", message);
+    sink::emit_line(&format!("[{}] {}", color::warn("warn"), message));
 }
 
 /// Log a status line with success/failure indicator
@@ -126,9 +240,9 @@ pub fn warn_simple(message: &str) {
 /// ```
 pub fn status(name: &str, message: &str, ok: bool) {
     if ok {
-        eprintln!("[ok] [{}] {}", name, message);
+        sink::emit_line(&format!("[{}] [{}] {}", color::ok("ok"), name, message));
     } else {
-        eprintln!("[fail] [{}] {}", name, message);
+        sink::emit_line(&format!("[{}] [{}] {}", color::fail("fail"), name, message));
     }
 }
 
@@ -143,14 +257,14 @@ pub fn status(name: &str, message: &str, ok: bool) {
 /// // ----------------------------------------
 /// ```
 pub fn header(title: &str) {
-    eprintln!();
-    eprintln!("{}", title);
-    eprintln!("{}", "-".repeat(40));
+    sink::emit_line("");
+    sink::emit_line(title);
+    sink::emit_line(&"-".repeat(40));
 }
 
 /// Print a blank line
 pub fn blank() {
-    eprintln!();
+    sink::emit_line("");
 }
 
 /// Success message
@@ -162,7 +276,7 @@ pub fn blank() {
 /// // Output: [ok] build complete
 /// ```
 pub fn success(message: &str) {
-    eprintln!("[ok] {}", message);
+    sink::emit_line(&format!("[{}] {}", color::ok("ok"), message));
 }
 
 /// Failure message
@@ -174,7 +288,7 @@ pub fn success(message: &str) {
 /// // Output: [fail] build failed
 /// ```
 pub fn fail(message: &str) {
-    eprintln!("[fail] {}", message);
+    sink::emit_line(&format!("[{}] {}", color::fail("fail"), message));
 }
 
 /// Info line with label
@@ -186,19 +300,19 @@ pub fn fail(message: &str) {
 /// // Output:   port       8506
 /// ```
 pub fn info(label: &str, value: &str) {
-    eprintln!("  {:<10} {}", label, value);
+    sink::emit_line(&format!("  {:<10} {}", label, value));
 }
 
 /// Hint in subdued format
 /// Format: `  message`
 pub fn hint(message: &str) {
-    eprintln!("  {}", message);
+    sink::emit_line(&format!("  {}", message));
 }
 
 /// Detail line with arrow
 /// Format: `    -> message`
 pub fn detail(message: &str) {
-    eprintln!("    -> {}", message);
+    sink::emit_line(&format!("    -> {}", message));
 }
 
 /// Suggest a next step
@@ -210,13 +324,13 @@ pub fn detail(message: &str) {
 /// // Output:   -> start the server: npm run dev
 /// ```
 pub fn next_step(description: &str, command: &str) {
-    eprintln!("  -> {}: {}", description, command);
+    sink::emit_line(&format!("  -> {}: {}", description, command));
 }
 
 /// Diagnostic warning
 /// Format: `[warn] [component] message`
 pub fn diagnostic(component: &str, message: &str) {
-    eprintln!("[warn] [{}] {}", component, message);
+    sink::emit_line(&format!("[{}] [{}] {}", color::warn("warn"), component, message));
 }
 
 // ============================================================
@@ -231,8 +345,8 @@ pub fn diagnostic(component: &str, message: &str) {
 /// // Output (only if LOG_LEVEL=debug): [cache] hit for key: user_123
 /// ```
 pub fn debug(action: &str, message: &str) {
-    if log_level() >= LogLevel::Debug {
-        eprintln!("[{}] {}", action, message);
+    if filter::enabled_for(action, LogLevel::Debug) {
+        sink::emit_line(&format!("[{}] {}", action, message));
     }
 }
 
@@ -240,35 +354,53 @@ pub fn debug(action: &str, message: &str) {
 // Macros for convenient formatting
 // ============================================================
 
-/// Log with format string support
+/// Log with format string support, and optional structured fields
 ///
 /// # Example
 /// ```
 /// tana_stdio::logf!("build", "compiled {} files in {}ms", 42, 150);
+/// tana_stdio::logf!("build", files = 42, ms = 150; "compiled");
 /// ```
 #[macro_export]
 macro_rules! logf {
+    ($action:expr, $($key:ident = $value:expr),+ ; $($arg:tt)*) => {
+        if $crate::filter::enabled_for($action, $crate::LogLevel::Info) {
+            $crate::kv::__emit("info", $action, &format!($($arg)*),
+                &[$((stringify!($key), $crate::Value::from($value))),+]);
+        }
+    };
     ($action:expr, $($arg:tt)*) => {
-        if $crate::log_level() >= $crate::LogLevel::Info {
-            eprintln!(concat!("[", $action, "] {}"), format!($($arg)*));
+        if $crate::filter::enabled_for($action, $crate::LogLevel::Info) {
+            $crate::kv::__emit("info", $action, &format!($($arg)*), &[]);
         }
     };
 }
 
-/// Error with format string support
+/// Error with format string support, and optional structured fields
 #[macro_export]
 macro_rules! errorf {
+    ($action:expr, $($key:ident = $value:expr),+ ; $($arg:tt)*) => {
+        $crate::kv::__emit("error", $action, &format!($($arg)*),
+            &[$((stringify!($key), $crate::Value::from($value))),+]);
+    };
     ($action:expr, $($arg:tt)*) => {
-        eprintln!(concat!("[", $action, "] {}"), format!($($arg)*));
+        $crate::kv::__emit("error", $action, &format!($($arg)*), &[]);
     };
 }
 
-/// Debug with format string support (only shown when LOG_LEVEL=debug)
+/// Debug with format string support, and optional structured fields (only shown when debug
+/// logging is enabled for the action, see [`filter::enabled_for`])
 #[macro_export]
 macro_rules! debugf {
+    ($action:expr, $($key:ident = $value:expr),+ ; $($arg:tt)*) => {
+        if $crate::filter::enabled_for($action, $crate::LogLevel::Debug) {
+            $crate::kv::__emit("debug", $action, &format!($($arg)*),
+                &[$((stringify!($key), $crate::Value::from($value))),+]);
+        }
+    };
     ($action:expr, $($arg:tt)*) => {
-        if $crate::log_level() >= $crate::LogLevel::Debug {
-            eprintln!(concat!("[", $action, "] {}"), format!($($arg)*));
+        if $crate::filter::enabled_for($action, $crate::LogLevel::Debug) {
+            $crate::kv::__emit("debug", $action, &format!($($arg)*), &[]);
         }
     };
 }
@@ -0,0 +1,105 @@
+//! Optional `log` crate facade.
+//!
+//! This lets libraries that already emit through the standard `log` macros
+//! (`info!`, `warn!`, `error!`, ...) route through tana-stdio's
+//! `[action] message` formatting instead of the `log` crate's own default
+//! output, while CLI tools keep using the direct API in the crate root.
+
+use log::{Level, Log, Metadata, Record, SetLoggerError};
+
+use crate::LogLevel;
+
+/// A [`log::Log`] implementation that renders records as `[<target>] <args>`,
+/// matching the crate's `[action] message` format.
+///
+/// The record's `target()` is used as the action, so `log::info!(target: "build", ...)`
+/// prints the same as `tana_stdio::log("build", ...)`. Every call consults
+/// [`crate::filter::enabled_for`] live, so [`crate::set_log_level`]/
+/// [`crate::with_log_level`] and `LOG_FILTER` directives apply to records
+/// logged through `log::*` exactly as they do to the direct API.
+pub struct TanaLogger;
+
+impl TanaLogger {
+    /// Create a logger. Verbosity is read live from the crate's runtime
+    /// level and filter rules, not fixed at construction time.
+    pub fn new() -> Self {
+        TanaLogger
+    }
+
+    fn from_log_level(level: Level) -> LogLevel {
+        match level {
+            Level::Error => LogLevel::Error,
+            Level::Warn | Level::Info => LogLevel::Info,
+            Level::Debug | Level::Trace => LogLevel::Debug,
+        }
+    }
+}
+
+impl Default for TanaLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Log for TanaLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        crate::filter::enabled_for(metadata.target(), Self::from_log_level(metadata.level()))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        crate::sink::emit_line(&format!("[{}] {}", record.target(), record.args()));
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install a [`TanaLogger`] as the global `log` backend at the calling
+/// thread's current [`LogLevel`] (see [`crate::log_level`]).
+///
+/// # Example
+/// ```
+/// tana_stdio::logger::init().ok();
+/// log::info!(target: "build", "compiling contract...");
+/// // Output: [build] compiling contract...
+/// ```
+pub fn init() -> Result<(), SetLoggerError> {
+    init_with_level(crate::log_level())
+}
+
+/// Install a [`TanaLogger`] as the global `log` backend, setting the calling
+/// thread's runtime level to `level` (via [`crate::set_log_level`]). Records
+/// logged on other threads are filtered against those threads' own level,
+/// which starts from `LOG_LEVEL`; `level` does not apply to them.
+///
+/// `log::set_max_level` is always set to `Trace` here: filtering is done by
+/// [`TanaLogger::enabled`] against the live runtime level and any
+/// `LOG_FILTER` directives, so the `log` crate's own static fast-path filter
+/// is kept fully open and never shadows a later `set_log_level`/
+/// `with_log_level` call.
+pub fn init_with_level(level: LogLevel) -> Result<(), SetLoggerError> {
+    crate::set_log_level(level);
+    log::set_max_level(log::LevelFilter::Trace);
+    log::set_boxed_logger(Box::new(TanaLogger::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(level: Level, target: &str) -> Metadata<'_> {
+        Metadata::builder().level(level).target(target).build()
+    }
+
+    #[test]
+    fn enabled_tracks_runtime_log_level_changes() {
+        let logger = TanaLogger::new();
+        crate::with_log_level(LogLevel::Error, || {
+            assert!(!logger.enabled(&metadata(Level::Debug, "anything")));
+            crate::set_log_level(LogLevel::Debug);
+            assert!(logger.enabled(&metadata(Level::Debug, "anything")));
+        });
+    }
+}
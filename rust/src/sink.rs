@@ -0,0 +1,95 @@
+//! Configurable output sink.
+//!
+//! Every logging function writes through this sink rather than calling
+//! `eprintln!` directly, so output can be captured in tests, redirected to a
+//! file, or fanned out by a downstream service. Defaults to stderr.
+
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+static WRITER: OnceLock<Mutex<Box<dyn Write + Send>>> = OnceLock::new();
+
+/// Whether the sink is still the default, never-redirected stderr writer.
+/// Used by [`crate::color`] to gate ANSI output: once a custom writer is
+/// installed we can't know whether its destination is a terminal, so color
+/// stays off regardless of the real stderr (unless forced with
+/// `LOG_COLOR=always`).
+static USING_DEFAULT: AtomicBool = AtomicBool::new(true);
+
+fn writer() -> &'static Mutex<Box<dyn Write + Send>> {
+    WRITER.get_or_init(|| Mutex::new(Box::new(io::stderr())))
+}
+
+/// Redirect all crate output to `writer` instead of stderr.
+///
+/// # Example
+/// ```
+/// tana_stdio::sink::set_writer(Box::new(std::io::sink()));
+/// ```
+pub fn set_writer(writer: Box<dyn Write + Send>) {
+    USING_DEFAULT.store(false, Ordering::Relaxed);
+    *self::writer().lock().unwrap() = writer;
+}
+
+/// True if output still goes to the default stderr writer and that stderr
+/// is a terminal. Unless `LOG_COLOR=always` forces it, color is only
+/// enabled when this holds.
+pub(crate) fn is_default_terminal() -> bool {
+    USING_DEFAULT.load(Ordering::Relaxed) && io::stderr().is_terminal()
+}
+
+/// Redirect output into an in-memory buffer, returning a handle the caller
+/// can read back to assert on formatted output.
+///
+/// # Example
+/// ```
+/// let buf = tana_stdio::sink::capture();
+/// tana_stdio::success("build complete");
+/// assert_eq!(
+///     String::from_utf8(buf.lock().unwrap().clone()).unwrap(),
+///     "[ok] build complete\n"
+/// );
+/// ```
+pub fn capture() -> Arc<Mutex<Vec<u8>>> {
+    let buf = Arc::new(Mutex::new(Vec::new()));
+    set_writer(Box::new(CaptureWriter(buf.clone())));
+    buf
+}
+
+struct CaptureWriter(Arc<Mutex<Vec<u8>>>);
+
+impl Write for CaptureWriter {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Write `line` (given without a trailing newline) to the current sink,
+/// followed by a newline.
+#[doc(hidden)]
+pub fn emit_line(line: &str) {
+    let mut w = writer().lock().unwrap();
+    let _ = writeln!(w, "{}", line);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capture_collects_formatted_output() {
+        let buf = capture();
+        crate::success("build complete");
+        crate::error("build", "compilation failed");
+        assert_eq!(
+            String::from_utf8(buf.lock().unwrap().clone()).unwrap(),
+            "[ok] build complete\n[build] compilation failed\n"
+        );
+    }
+}